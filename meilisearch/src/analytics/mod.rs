@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use actix_web::HttpRequest;
+
+use crate::search::SearchQueryWithIndex;
+
+/// A sink for search analytics events, implemented by whatever event backend the
+/// instance is configured to report to (or a no-op when analytics are disabled).
+///
+/// Only the `/multi-search` event is declared here.
+pub trait Analytics: Send + Sync {
+    /// Report a completed `/multi-search` request.
+    fn post_multi_search(&self, aggregate: MultiSearchAggregator);
+}
+
+/// Aggregates analytics for the `/multi-search` route.
+///
+/// Like the other route aggregators, a `MultiSearchAggregator` is built once per
+/// request and merged into the event backend's running totals for the current
+/// reporting window, so its fields are running maximums/counters rather than
+/// per-request values.
+#[derive(Debug, Default)]
+pub struct MultiSearchAggregator {
+    total_received: usize,
+    total_succeeded: usize,
+    /// The longest sub-query duration reported by any request in this window.
+    max_sub_query_duration: Duration,
+    /// The highest concurrency factor (number of sub-queries dispatched together)
+    /// seen across the requests in this window.
+    max_concurrency_factor: u32,
+}
+
+impl MultiSearchAggregator {
+    pub fn from_queries(queries: &[SearchQueryWithIndex], _req: &HttpRequest) -> Self {
+        Self { total_received: queries.len(), ..Default::default() }
+    }
+
+    pub fn succeed(&mut self) {
+        self.total_succeeded += 1;
+    }
+
+    /// Record a request's reported sub-query duration and concurrency factor.
+    ///
+    /// Both are folded in as a running maximum: this aggregator tracks the worst
+    /// case seen in the reporting window, not a per-request value or a sum across
+    /// requests.
+    pub fn set_sub_query_duration(&mut self, duration: Duration, concurrency_factor: u32) {
+        self.max_sub_query_duration = self.max_sub_query_duration.max(duration);
+        self.max_concurrency_factor = self.max_concurrency_factor.max(concurrency_factor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_sub_query_duration_keeps_the_running_maximum_of_each_field() {
+        let mut aggregate = MultiSearchAggregator::default();
+
+        // A sequential request reporting a long summed duration but no concurrency.
+        aggregate.set_sub_query_duration(Duration::from_millis(750), 1);
+        assert_eq!(aggregate.max_sub_query_duration, Duration::from_millis(750));
+        assert_eq!(aggregate.max_concurrency_factor, 1);
+
+        // A later concurrent request with a shorter duration but a higher factor:
+        // the duration max must not regress, and the factor max must pick it up.
+        aggregate.set_sub_query_duration(Duration::from_millis(200), 5);
+        assert_eq!(aggregate.max_sub_query_duration, Duration::from_millis(750));
+        assert_eq!(aggregate.max_concurrency_factor, 5);
+
+        // A later request beating both previous maximums updates both fields.
+        aggregate.set_sub_query_duration(Duration::from_millis(900), 8);
+        assert_eq!(aggregate.max_sub_query_duration, Duration::from_millis(900));
+        assert_eq!(aggregate.max_concurrency_factor, 8);
+    }
+}
@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
 use actix_web::web::{self, Data};
 use actix_web::{HttpRequest, HttpResponse};
 use index_scheduler::IndexScheduler;
@@ -5,6 +8,7 @@ use log::debug;
 use meilisearch_types::deserr::DeserrJsonError;
 use meilisearch_types::error::ResponseError;
 use meilisearch_types::keys::actions;
+use serde::Deserialize;
 
 use crate::analytics::{Analytics, MultiSearchAggregator};
 use crate::extractors::authentication::policies::ActionPolicy;
@@ -19,37 +23,100 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(web::resource("").route(web::post().to(SeqHandler(search_with_post))));
 }
 
+/// Query parameters accepted alongside the multi-search request body.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSearchParams {
+    /// When `true`, independent sub-queries are dispatched onto the blocking thread
+    /// pool together instead of being awaited one at a time, so the overall latency
+    /// is closer to the slowest sub-query than to their sum. Defaults to `false` to
+    /// keep the historical sequential semantics.
+    #[serde(default)]
+    concurrent: bool,
+}
+
 pub async fn search_with_post(
     index_scheduler: GuardedData<ActionPolicy<{ actions::SEARCH }>, Data<IndexScheduler>>,
     params: ValidatedJson<Vec<SearchQueryWithIndex>, DeserrJsonError>,
+    query_params: web::Query<MultiSearchParams>,
     req: HttpRequest,
     analytics: web::Data<dyn Analytics>,
 ) -> Result<HttpResponse, ResponseError> {
     let queries = params.into_inner();
+    let concurrent = query_params.concurrent;
 
     let mut multi_aggregate = MultiSearchAggregator::from_queries(&queries, &req);
 
     let search_results: Result<_, ResponseError> = (|| {
         async {
-            let mut search_results = Vec::with_capacity(queries.len());
+            // Tenant token search_rules, and dedup index lookups: several sub-queries
+            // commonly target the same index, so each one is opened at most once and
+            // its handle is shared across its queries. Only the handle is shared: each
+            // sub-query is still passed to `perform_search` on its own.
+            let mut indexes = HashMap::new();
+            let mut queries_by_index = Vec::with_capacity(queries.len());
             for (index_uid, mut query) in
                 queries.into_iter().map(SearchQueryWithIndex::into_index_query)
             {
                 debug!("search called with params: {:?}", query);
 
-                // Tenant token search_rules.
                 if let Some(search_rules) =
                     index_scheduler.filters().search_rules.get_index_search_rules(&index_uid)
                 {
                     add_search_rules(&mut query, search_rules);
                 }
 
-                let index = index_scheduler.index(&index_uid)?;
-                let search_result =
-                    tokio::task::spawn_blocking(move || perform_search(&index, query)).await?;
+                if !indexes.contains_key(&index_uid) {
+                    let index = index_scheduler.index(&index_uid)?;
+                    indexes.insert(index_uid.clone(), index);
+                }
+                queries_by_index.push((index_uid, query));
+            }
+
+            let n_queries = queries_by_index.len();
+            let mut search_results = Vec::with_capacity(n_queries);
+            // The concurrent path reports the slowest sub-query, since wall-clock time
+            // for the whole request is driven by it; the sequential path instead sums
+            // every sub-query's duration, since they run one after another and the
+            // wall-clock cost is their total, not any single one of them.
+            let mut reported_duration = Duration::ZERO;
 
-                search_results.push(SearchResultWithIndex { index_uid, result: search_result? });
+            if concurrent {
+                // Spawn every sub-query onto the blocking pool up front so they run
+                // together, then join them back in the original order.
+                let handles: Vec<_> = queries_by_index
+                    .into_iter()
+                    .map(|(index_uid, query)| {
+                        let index = indexes[&index_uid].clone();
+                        tokio::task::spawn_blocking(move || {
+                            let start = Instant::now();
+                            let result = perform_search(&index, query);
+                            (index_uid, result, start.elapsed())
+                        })
+                    })
+                    .collect();
+                for handle in handles {
+                    let (index_uid, result, duration) = handle.await?;
+                    reported_duration = reported_duration.max(duration);
+                    search_results.push(SearchResultWithIndex { index_uid, result: result? });
+                }
+            } else {
+                for (index_uid, query) in queries_by_index {
+                    let index = indexes[&index_uid].clone();
+                    let (index_uid, result, duration) = tokio::task::spawn_blocking(move || {
+                        let start = Instant::now();
+                        let result = perform_search(&index, query);
+                        (index_uid, result, start.elapsed())
+                    })
+                    .await?;
+                    reported_duration += duration;
+                    search_results.push(SearchResultWithIndex { index_uid, result: result? });
+                }
             }
+
+            let concurrency_factor = if concurrent { n_queries as u32 } else { 1 };
+            multi_aggregate.set_sub_query_duration(reported_duration, concurrency_factor);
+
             Ok(search_results)
         }
     })()
@@ -0,0 +1,49 @@
+//! Benchmarks for `PathsMap`'s fan-out handling: a node with hundreds of edges used
+//! to be scanned linearly on every `insert`/`remove_edges` call, which dominated
+//! path-map construction and pruning time on dense proximity graphs. The linear-scan
+//! representation has since been fully replaced by the bitmap-indexed one, so these
+//! benchmarks no longer compare against it directly; they instead measure the current
+//! representation at increasing fan-outs as a regression guard, so a future change
+//! that reintroduces linear scanning shows up as a clear regression here.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use milli::search::new::ranking_rule_graph::PathsMap;
+use roaring::RoaringBitmap;
+
+fn build_fan_out(fan_out: u32) -> PathsMap<u64> {
+    let mut map = PathsMap::default();
+    for edge in 0..fan_out {
+        map.insert(std::iter::once(edge), edge as u64);
+    }
+    map
+}
+
+fn bench_insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("paths_map_insert");
+    for fan_out in [10, 100, 1000] {
+        group.bench_with_input(BenchmarkId::from_parameter(fan_out), &fan_out, |b, &fan_out| {
+            b.iter(|| build_fan_out(fan_out));
+        });
+    }
+    group.finish();
+}
+
+fn bench_remove_edges(c: &mut Criterion) {
+    let mut group = c.benchmark_group("paths_map_remove_edges");
+    for fan_out in [10, 100, 1000] {
+        // Remove every other edge, which forces a recursive descent alongside the
+        // single-shot bitmap intersection.
+        let forbidden: RoaringBitmap = (0..fan_out).step_by(2).collect();
+        group.bench_with_input(BenchmarkId::from_parameter(fan_out), &fan_out, |b, &fan_out| {
+            b.iter_batched(
+                || build_fan_out(fan_out),
+                |mut map| map.remove_edges(&forbidden),
+                criterion::BatchSize::SmallInput,
+            );
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_insert, bench_remove_edges);
+criterion_main!(benches);
@@ -0,0 +1,58 @@
+mod visual;
+
+pub use visual::VisualSearchLogger;
+
+use super::ranking_rule_graph::{DeadEndsCache, PathsMap, RankingRuleGraph, RankingRuleGraphTrait};
+use crate::new::cheapest_paths::Path;
+
+/// A hook into the graph-based ranking rules (proximity, typo) that lets a caller
+/// observe, at every iteration, the state of the [`RankingRuleGraph`] and the
+/// candidate [`PathsMap`] it is choosing from.
+///
+/// The default [`NoopSearchLogger`] does nothing so that production search pays
+/// nothing for this instrumentation; implementations meant for debugging (such as
+/// [`VisualSearchLogger`]) can instead buffer every iteration and render it later.
+pub trait SearchLogger<G: RankingRuleGraphTrait> {
+    /// Called once per iteration of a graph ranking rule's bucket loop.
+    ///
+    /// `graph` and `paths` are the ranking rule graph and candidate paths considered
+    /// at this iteration, `dead_ends` is the cache of edge combinations pruned before
+    /// resolution, `chosen` is the path that was ultimately selected (if any), and
+    /// `universe_len` is the number of docids left in the resolved bucket.
+    /// `edge_docids_len` returns the cardinality of an edge's already-resolved docids,
+    /// or `None` if it hasn't been resolved yet; it is only meant for display, so
+    /// implementations are free to ignore it.
+    fn log_iteration(
+        &mut self,
+        graph: &RankingRuleGraph<G>,
+        paths: &PathsMap<u64>,
+        dead_ends: &DeadEndsCache,
+        chosen: Option<&Path>,
+        universe_len: u64,
+        edge_docids_len: &dyn Fn(u32) -> Option<u64>,
+    );
+
+    /// Called once the ranking rule has produced all of its buckets, so the logger
+    /// can flush any buffered output.
+    fn finish(&mut self);
+}
+
+/// A [`SearchLogger`] that discards every iteration. This is the implementation used
+/// in production.
+#[derive(Default)]
+pub struct NoopSearchLogger;
+
+impl<G: RankingRuleGraphTrait> SearchLogger<G> for NoopSearchLogger {
+    fn log_iteration(
+        &mut self,
+        _graph: &RankingRuleGraph<G>,
+        _paths: &PathsMap<u64>,
+        _dead_ends: &DeadEndsCache,
+        _chosen: Option<&Path>,
+        _universe_len: u64,
+        _edge_docids_len: &dyn Fn(u32) -> Option<u64>,
+    ) {
+    }
+
+    fn finish(&mut self) {}
+}
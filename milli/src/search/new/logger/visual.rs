@@ -0,0 +1,190 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+use super::SearchLogger;
+use crate::new::cheapest_paths::Path;
+use crate::new::ranking_rule_graph::{DeadEndsCache, PathsMap, RankingRuleGraph, RankingRuleGraphTrait};
+
+struct Frame {
+    paths_dot: String,
+    graph_dot: String,
+    chosen: Option<Vec<u32>>,
+    universe_len: u64,
+}
+
+/// A [`SearchLogger`] that records the graph and candidate paths at every iteration
+/// of a graph ranking rule, and on [`finish`](Self::finish) writes them all to a
+/// single self-contained HTML file that can be opened locally to replay the search
+/// step by step: which paths were considered, which one was picked, and why the
+/// cheaper alternatives were discarded.
+pub struct VisualSearchLogger {
+    output_path: PathBuf,
+    frames: Vec<Frame>,
+}
+
+impl VisualSearchLogger {
+    pub fn new(output_path: impl Into<PathBuf>) -> Self {
+        Self { output_path: output_path.into(), frames: vec![] }
+    }
+}
+
+impl<G: RankingRuleGraphTrait> SearchLogger<G> for VisualSearchLogger {
+    fn log_iteration(
+        &mut self,
+        graph: &RankingRuleGraph<G>,
+        paths: &PathsMap<u64>,
+        dead_ends: &DeadEndsCache,
+        chosen: Option<&Path>,
+        universe_len: u64,
+        edge_docids_len: &dyn Fn(u32) -> Option<u64>,
+    ) {
+        let paths_dot = paths.graphviz(
+            graph,
+            chosen.map(|p| p.edges.as_slice()),
+            dead_ends.empty_edges(),
+            &edge_docids_len,
+        );
+        let highlighted = Path {
+            edges: chosen.map(|p| p.edges.clone()).unwrap_or_default(),
+            cost: chosen.map(|p| p.cost).unwrap_or(0),
+        };
+        let graph_dot =
+            graph.graphviz_with_path(&highlighted, dead_ends.empty_edges(), &edge_docids_len);
+        self.frames.push(Frame {
+            paths_dot,
+            graph_dot,
+            chosen: chosen.map(|p| p.edges.clone()),
+            universe_len,
+        });
+    }
+
+    fn finish(&mut self) {
+        let html = self.render_html();
+
+        // Best-effort: a failure to write the debug trace should never take down
+        // the search itself.
+        if let Ok(mut file) = File::create(&self.output_path) {
+            let _ = file.write_all(html.as_bytes());
+        }
+    }
+}
+
+impl VisualSearchLogger {
+    /// Build the self-contained HTML timeline from the buffered frames.
+    ///
+    /// Split out from [`finish`](SearchLogger::finish) so the templating can be unit
+    /// tested without writing to disk.
+    fn render_html(&self) -> String {
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str("<title>Search logger</title>\n");
+        html.push_str("<script src=\"https://unpkg.com/viz.js@2.1.2/viz.js\"></script>\n");
+        html.push_str("<script src=\"https://unpkg.com/viz.js@2.1.2/full.render.js\"></script>\n");
+        html.push_str("</head>\n<body>\n");
+        html.push_str("<div id=\"info\"></div>\n");
+        html.push_str("<button onclick=\"toggleView()\">toggle graph / paths</button>\n");
+        html.push_str("<div id=\"graph\"></div>\n");
+        html.push_str("<button onclick=\"step(-1)\">previous</button>\n");
+        html.push_str("<button onclick=\"step(1)\">next</button>\n");
+
+        html.push_str("<script>\nconst frames = [\n");
+        for frame in &self.frames {
+            let chosen = match &frame.chosen {
+                Some(edges) => format!("{edges:?}"),
+                None => "null".to_owned(),
+            };
+            html.push_str(&format!(
+                "{{ pathsDot: {paths_dot:?}, graphDot: {graph_dot:?}, chosen: {chosen}, universeLen: {universe_len} }},\n",
+                paths_dot = frame.paths_dot,
+                graph_dot = frame.graph_dot,
+                universe_len = frame.universe_len,
+            ));
+        }
+        html.push_str("];\n");
+        html.push_str(
+            r#"
+let current = 0;
+let showGraph = false;
+const viz = new Viz();
+function render() {
+    const frame = frames[current];
+    document.getElementById('info').innerText =
+        `iteration ${current + 1}/${frames.length} — chosen path: ${frame.chosen} — universe: ${frame.universeLen} docids`;
+    viz.renderSVGElement(showGraph ? frame.graphDot : frame.pathsDot).then(svg => {
+        const graph = document.getElementById('graph');
+        graph.innerHTML = '';
+        graph.appendChild(svg);
+    });
+}
+function step(delta) {
+    current = Math.max(0, Math.min(frames.length - 1, current + delta));
+    render();
+}
+function toggleView() {
+    showGraph = !showGraph;
+    render();
+}
+render();
+"#,
+        );
+        html.push_str("</script>\n</body>\n</html>\n");
+
+        html
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(paths_dot: &str, graph_dot: &str, chosen: Option<Vec<u32>>, universe_len: u64) -> Frame {
+        Frame { paths_dot: paths_dot.to_owned(), graph_dot: graph_dot.to_owned(), chosen, universe_len }
+    }
+
+    #[test]
+    fn render_html_embeds_every_frame_in_order() {
+        let logger = VisualSearchLogger {
+            output_path: "unused.html".into(),
+            frames: vec![
+                frame("digraph G { paths 1 }", "digraph G { graph 1 }", Some(vec![0, 2]), 42),
+                frame("digraph G { paths 2 }", "digraph G { graph 2 }", None, 7),
+            ],
+        };
+
+        let html = logger.render_html();
+
+        assert_eq!(html.matches("pathsDot:").count(), 2);
+        assert!(html.contains("digraph G { paths 1 }"));
+        assert!(html.contains("digraph G { graph 1 }"));
+        assert!(html.contains("digraph G { paths 2 }"));
+        assert!(html.contains("digraph G { graph 2 }"));
+    }
+
+    #[test]
+    fn render_html_formats_the_chosen_path_as_an_array_or_null() {
+        let logger = VisualSearchLogger {
+            output_path: "unused.html".into(),
+            frames: vec![
+                frame("p", "g", Some(vec![3, 1, 4]), 1),
+                frame("p", "g", None, 1),
+            ],
+        };
+
+        let html = logger.render_html();
+
+        assert!(html.contains("chosen: [3, 1, 4]"));
+        assert!(html.contains("chosen: null"));
+    }
+
+    #[test]
+    fn render_html_is_well_formed_for_no_frames() {
+        let logger = VisualSearchLogger { output_path: "unused.html".into(), frames: vec![] };
+
+        let html = logger.render_html();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.trim_end().ends_with("</html>"));
+        assert_eq!(html.matches("pathsDot:").count(), 0);
+    }
+}
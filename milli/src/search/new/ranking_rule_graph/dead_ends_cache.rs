@@ -0,0 +1,165 @@
+use roaring::RoaringBitmap;
+
+use super::PathsMap;
+
+/// A cache of edge combinations already known to resolve to an empty docid set.
+///
+/// Path resolution is expensive: each candidate path has to be turned into a docid
+/// bitmap by intersecting one LMDB-backed bitmap per edge. Many of the paths produced
+/// by [`RankingRuleGraph::k_cheapest_paths`](super::RankingRuleGraph::k_cheapest_paths)
+/// share a prefix that has already been proven to resolve to an empty set, so this
+/// cache lets the caller discard them before touching the database again.
+///
+/// Two kinds of dead ends are tracked:
+/// - single edges whose own docids are empty, which can be stripped from any
+///   candidate set in one shot via [`PathsMap::remove_edges`];
+/// - edge *combinations* that are individually non-empty but whose intersection is,
+///   stored as the shortest prefix that was proven empty so that every longer path
+///   sharing it is pruned for free.
+#[derive(Debug, Default)]
+pub struct DeadEndsCache {
+    empty_edges: RoaringBitmap,
+    empty_prefixes: PathsMap<()>,
+}
+
+impl DeadEndsCache {
+    /// Record that the docids of a single edge are empty.
+    pub fn forbid_edge(&mut self, edge_idx: u32) {
+        self.empty_edges.insert(edge_idx);
+    }
+
+    /// Record that the intersection of the docids along `prefix` is empty.
+    ///
+    /// Callers should only ever pass a prefix that was proven empty by an actual
+    /// intersection: forbidding a prefix that a non-empty path depends on would make
+    /// `prune` silently discard valid results.
+    pub fn forbid_prefix(&mut self, prefix: &[u32]) {
+        self.empty_prefixes.insert(prefix.iter().copied(), ());
+    }
+
+    /// Given the edges of a path that just resolved to an empty bitmap, figure out
+    /// the shortest prefix responsible for it by intersecting edges left to right,
+    /// and remember it.
+    ///
+    /// `edge_docids` is called once per edge, in path order, and must return the
+    /// (already resolved) docids for that edge.
+    pub fn forbid_shortest_responsible_prefix(
+        &mut self,
+        path: &[u32],
+        mut edge_docids: impl FnMut(u32) -> RoaringBitmap,
+    ) {
+        let mut running = None::<RoaringBitmap>;
+        for (idx, &edge_idx) in path.iter().enumerate() {
+            let docids = edge_docids(edge_idx);
+            running = Some(match running.take() {
+                Some(acc) => acc & docids,
+                None => docids,
+            });
+            if running.as_ref().unwrap().is_empty() {
+                self.forbid_prefix(&path[0..=idx]);
+                return;
+            }
+        }
+    }
+
+    /// Whether `path` extends a known dead end, in O(path length).
+    pub fn contains_prefix_of_path(&self, path: &[u32]) -> bool {
+        self.empty_prefixes.contains_prefix_of_path(path)
+    }
+
+    /// The edges individually known to resolve to an empty docid set, for callers
+    /// that only want a flat view of the cache (e.g. to grey them out in a graph
+    /// visualization).
+    pub fn empty_edges(&self) -> &RoaringBitmap {
+        &self.empty_edges
+    }
+
+    /// Remove every path in `candidates` that is known to be a dead end, either
+    /// because it contains a globally empty edge or because it extends a prefix
+    /// already proven empty.
+    pub fn prune<V>(&self, candidates: &mut PathsMap<V>) {
+        candidates.remove_edges(&self.empty_edges);
+        candidates.remove_prefixes(&self.empty_prefixes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn docids(ids: &[u32]) -> RoaringBitmap {
+        ids.iter().copied().collect()
+    }
+
+    #[test]
+    fn forbid_shortest_responsible_prefix_stores_the_prefix_where_the_intersection_empties() {
+        let mut cache = DeadEndsCache::default();
+        // edge 0 and edge 1 share no docid: the running intersection goes empty as soon
+        // as edge 1 is folded in, so the recorded prefix must be path[0..=1], not
+        // path[0..=0] or the full path.
+        let path = [0, 1, 2];
+        let edge_docids = |edge_idx: u32| match edge_idx {
+            0 => docids(&[7, 8]),
+            1 => docids(&[9]),
+            2 => docids(&[1, 2]),
+            _ => unreachable!(),
+        };
+
+        cache.forbid_shortest_responsible_prefix(&path, edge_docids);
+
+        assert!(cache.contains_prefix_of_path(&[0, 1]));
+        assert!(cache.contains_prefix_of_path(&[0, 1, 2]));
+        assert!(!cache.contains_prefix_of_path(&[0]));
+        assert!(!cache.contains_prefix_of_path(&[0, 2]));
+    }
+
+    #[test]
+    fn forbid_shortest_responsible_prefix_does_nothing_if_the_full_intersection_is_non_empty() {
+        let mut cache = DeadEndsCache::default();
+        let path = [0, 1];
+        let edge_docids = |edge_idx: u32| match edge_idx {
+            0 => docids(&[7, 8]),
+            1 => docids(&[7]),
+            _ => unreachable!(),
+        };
+
+        cache.forbid_shortest_responsible_prefix(&path, edge_docids);
+
+        assert!(!cache.contains_prefix_of_path(&[0]));
+        assert!(!cache.contains_prefix_of_path(&[0, 1]));
+    }
+
+    #[test]
+    fn prune_only_removes_candidates_under_the_forbidden_prefix() {
+        let mut cache = DeadEndsCache::default();
+        cache.forbid_prefix(&[0, 1]);
+
+        let mut candidates = PathsMap::default();
+        candidates.insert([0, 1, 2].into_iter(), ());
+        candidates.insert([0, 3].into_iter(), ());
+        candidates.insert([4].into_iter(), ());
+
+        cache.prune(&mut candidates);
+
+        let mut remaining = vec![];
+        candidates.iterate(|path, _| remaining.push(path.clone()));
+        remaining.sort();
+        assert_eq!(remaining, vec![vec![0, 3], vec![4]]);
+    }
+
+    #[test]
+    fn prune_removes_candidates_through_a_globally_empty_edge() {
+        let mut cache = DeadEndsCache::default();
+        cache.forbid_edge(1);
+
+        let mut candidates = PathsMap::default();
+        candidates.insert([0, 1].into_iter(), ());
+        candidates.insert([0, 2].into_iter(), ());
+
+        cache.prune(&mut candidates);
+
+        let mut remaining = vec![];
+        candidates.iterate(|path, _| remaining.push(path.clone()));
+        assert_eq!(remaining, vec![vec![0, 2]]);
+    }
+}
@@ -1,5 +1,6 @@
+use std::cmp::Reverse;
 use std::collections::hash_map::DefaultHasher;
-use std::collections::HashSet;
+use std::collections::{BinaryHeap, HashSet};
 use std::fmt::Write;
 use std::hash::{Hash, Hasher};
 
@@ -9,14 +10,21 @@ use super::cheapest_paths::Path;
 use super::{Edge, EdgeDetails, RankingRuleGraph, RankingRuleGraphTrait};
 use crate::new::QueryNode;
 
+/// A node of the [`PathsMap`] trie.
+///
+/// Children are stored as a `RoaringBitmap` of the first-edges present at this node,
+/// plus a parallel vector of the corresponding subtries kept in the same (ascending)
+/// edge order: looking up the child for a given edge is a `contains` check followed
+/// by a `rank`-based index into `children` (see `benches/paths_map.rs` for why).
 #[derive(Debug)]
 pub struct PathsMap<V> {
-    nodes: Vec<(u32, PathsMap<V>)>,
+    present: RoaringBitmap,
+    children: Vec<PathsMap<V>>,
     value: Option<V>,
 }
 impl<V> Default for PathsMap<V> {
     fn default() -> Self {
-        Self { nodes: vec![], value: None }
+        Self { present: RoaringBitmap::new(), children: vec![], value: None }
     }
 }
 
@@ -34,7 +42,17 @@ impl PathsMap<u64> {
 }
 impl<V> PathsMap<V> {
     pub fn is_empty(&self) -> bool {
-        self.nodes.is_empty() && self.value.is_none()
+        self.present.is_empty() && self.value.is_none()
+    }
+
+    /// The index of the child for `edge`, if present, or the index at which it should
+    /// be inserted to keep `children` in ascending edge order otherwise.
+    fn child_index(&self, edge: u32) -> Result<usize, usize> {
+        if self.present.contains(edge) {
+            Ok((self.present.rank(edge) - 1) as usize)
+        } else {
+            Err(self.present.rank(edge) as usize)
+        }
     }
 
     pub fn insert(&mut self, mut edges: impl Iterator<Item = u32>, value: V) {
@@ -42,30 +60,29 @@ impl<V> PathsMap<V> {
             None => {
                 self.value = Some(value);
             }
-            Some(first_edge) => {
-                // comment
-                for (edge, next_node) in &mut self.nodes {
-                    if edge == &first_edge {
-                        return next_node.insert(edges, value);
-                    }
+            Some(first_edge) => match self.child_index(first_edge) {
+                Ok(idx) => self.children[idx].insert(edges, value),
+                Err(idx) => {
+                    let mut rest = PathsMap::default();
+                    rest.insert(edges, value);
+                    self.present.insert(first_edge);
+                    self.children.insert(idx, rest);
                 }
-                let mut rest = PathsMap::default();
-                rest.insert(edges, value);
-                self.nodes.push((first_edge, rest));
-            }
+            },
         }
     }
     fn remove_first_rec(&mut self, cur: &mut Vec<u32>) -> (bool, V) {
-        let Some((first_edge, rest)) = self.nodes.first_mut() else { 
+        let Some(first_edge) = self.present.min() else {
             // The PathsMap has to be correct by construction here, otherwise
             // the unwrap() will crash
-            return (true, self.value.take().unwrap()) 
+            return (true, self.value.take().unwrap())
         };
-        cur.push(*first_edge);
-        let (rest_is_empty, value) = rest.remove_first_rec(cur);
+        cur.push(first_edge);
+        let (rest_is_empty, value) = self.children[0].remove_first_rec(cur);
         if rest_is_empty {
-            self.nodes.remove(0);
-            (self.nodes.is_empty(), value)
+            self.present.remove(first_edge);
+            self.children.remove(0);
+            (self.present.is_empty(), value)
         } else {
             (false, value)
         }
@@ -83,8 +100,8 @@ impl<V> PathsMap<V> {
         if let Some(value) = &self.value {
             visit(cur, value);
         }
-        for (first_edge, rest) in self.nodes.iter() {
-            cur.push(*first_edge);
+        for (first_edge, rest) in self.present.iter().zip(self.children.iter()) {
+            cur.push(first_edge);
             rest.iterate_rec(cur, visit);
             cur.pop();
         }
@@ -99,18 +116,21 @@ impl<V> PathsMap<V> {
         });
     }
     pub fn remove_edges(&mut self, forbidden_edges: &RoaringBitmap) {
+        // Edges forbidden at this level are dropped in one shot via a bitmap
+        // intersection, instead of scanning `children` once per forbidden edge.
+        for edge in &self.present & forbidden_edges {
+            let idx = (self.present.rank(edge) - 1) as usize;
+            self.present.remove(edge);
+            self.children.remove(idx);
+        }
+
         let mut i = 0;
-        while i < self.nodes.len() {
-            let should_remove = if forbidden_edges.contains(self.nodes[i].0) {
-                true
-            } else if !self.nodes[i].1.nodes.is_empty() {
-                self.nodes[i].1.remove_edges(forbidden_edges);
-                self.nodes[i].1.nodes.is_empty()
-            } else {
-                false
-            };
-            if should_remove {
-                self.nodes.remove(i);
+        while i < self.children.len() {
+            self.children[i].remove_edges(forbidden_edges);
+            if self.children[i].is_empty() {
+                let edge = self.present.select(i as u32).unwrap();
+                self.present.remove(edge);
+                self.children.remove(i);
             } else {
                 i += 1;
             }
@@ -118,17 +138,19 @@ impl<V> PathsMap<V> {
     }
     pub fn remove_edge(&mut self, forbidden_edge: &u32) {
         let mut i = 0;
-        while i < self.nodes.len() {
-            let should_remove = if &self.nodes[i].0 == forbidden_edge {
+        while i < self.children.len() {
+            let edge = self.present.select(i as u32).unwrap();
+            let should_remove = if &edge == forbidden_edge {
                 true
-            } else if !self.nodes[i].1.nodes.is_empty() {
-                self.nodes[i].1.remove_edge(forbidden_edge);
-                self.nodes[i].1.nodes.is_empty()
+            } else if !self.children[i].is_empty() {
+                self.children[i].remove_edge(forbidden_edge);
+                self.children[i].is_empty()
             } else {
                 false
             };
             if should_remove {
-                self.nodes.remove(i);
+                self.present.remove(edge);
+                self.children.remove(i);
             } else {
                 i += 1;
             }
@@ -136,38 +158,29 @@ impl<V> PathsMap<V> {
     }
     pub fn remove_prefix(&mut self, forbidden_prefix: &[u32]) {
         let [first_edge, remaining_prefix @ ..] = forbidden_prefix else {
-            self.nodes.clear();
+            self.present = RoaringBitmap::new();
+            self.children.clear();
             self.value = None;
             return;
         };
 
-        let mut i = 0;
-        while i < self.nodes.len() {
-            let edge = self.nodes[i].0;
-            let should_remove = if edge == *first_edge {
-                self.nodes[i].1.remove_prefix(remaining_prefix);
-                self.nodes[i].1.nodes.is_empty()
-            } else {
-                false
-            };
-            if should_remove {
-                self.nodes.remove(i);
-            } else {
-                i += 1;
+        if let Ok(idx) = self.child_index(*first_edge) {
+            self.children[idx].remove_prefix(remaining_prefix);
+            if self.children[idx].is_empty() {
+                self.present.remove(*first_edge);
+                self.children.remove(idx);
             }
         }
     }
 
     pub fn edge_indices_after_prefix(&self, prefix: &[u32]) -> Vec<u32> {
         let [first_edge, remaining_prefix @ ..] = prefix else {
-            return self.nodes.iter().map(|n| n.0).collect();
+            return self.present.iter().collect();
         };
-        for (edge, rest) in self.nodes.iter() {
-            if edge == first_edge {
-                return rest.edge_indices_after_prefix(remaining_prefix);
-            }
+        match self.child_index(*first_edge) {
+            Ok(idx) => self.children[idx].edge_indices_after_prefix(remaining_prefix),
+            Err(_) => vec![],
         }
-        vec![]
     }
 
     pub fn contains_prefix_of_path(&self, path: &[u32]) -> bool {
@@ -176,21 +189,36 @@ impl<V> PathsMap<V> {
         }
         match path {
             [] => false,
-            [first_edge, remaining_path @ ..] => {
-                for (edge, rest) in self.nodes.iter() {
-                    if edge == first_edge {
-                        return rest.contains_prefix_of_path(remaining_path);
-                    }
-                }
-                false
-            }
+            [first_edge, remaining_path @ ..] => match self.child_index(*first_edge) {
+                Ok(idx) => self.children[idx].contains_prefix_of_path(remaining_path),
+                Err(_) => false,
+            },
         }
     }
 
-    pub fn graphviz<G: RankingRuleGraphTrait>(&self, graph: &RankingRuleGraph<G>) -> String {
+    /// Render this set of candidate paths as a DOT graph.
+    ///
+    /// `highlighted_path`, if given, is drawn in red; edges in `dead_ends` are
+    /// greyed out since they are known to resolve to an empty docid set; and
+    /// `edge_docids_len`, when it returns `Some`, annotates an edge with the
+    /// cardinality of its resolved docids.
+    pub fn graphviz<G: RankingRuleGraphTrait>(
+        &self,
+        graph: &RankingRuleGraph<G>,
+        highlighted_path: Option<&[u32]>,
+        dead_ends: &RoaringBitmap,
+        edge_docids_len: &impl Fn(u32) -> Option<u64>,
+    ) -> String {
         let mut desc = String::new();
         desc.push_str("digraph G {\n");
-        self.graphviz_rec(&mut desc, vec![], graph);
+        self.graphviz_rec(
+            &mut desc,
+            vec![],
+            graph,
+            highlighted_path.unwrap_or(&[]),
+            dead_ends,
+            edge_docids_len,
+        );
         desc.push_str("\n}\n");
         desc
     }
@@ -199,14 +227,17 @@ impl<V> PathsMap<V> {
         desc: &mut String,
         path_from: Vec<u64>,
         graph: &RankingRuleGraph<G>,
+        highlighted_path: &[u32],
+        dead_ends: &RoaringBitmap,
+        edge_docids_len: &impl Fn(u32) -> Option<u64>,
     ) {
         let id_from = {
             let mut h = DefaultHasher::new();
             path_from.hash(&mut h);
             h.finish()
         };
-        for (edge_idx, rest) in self.nodes.iter() {
-            let Some(Edge { from_node, to_node, cost, details }) = graph.all_edges[*edge_idx as usize].as_ref() else {
+        for (edge_idx, rest) in self.present.iter().zip(self.children.iter()) {
+            let Some(Edge { from_node, to_node, cost, details }) = graph.all_edges[edge_idx as usize].as_ref() else {
                 continue;
             };
             let mut path_to = path_from.clone();
@@ -220,16 +251,39 @@ impl<V> PathsMap<V> {
                 path_to.hash(&mut h);
                 h.finish()
             };
-            writeln!(desc, "{id_to} [label = \"{from_node}→{to_node} [{cost}]\"];").unwrap();
-            writeln!(desc, "{id_from} -> {id_to};").unwrap();
+            let color = if highlighted_path.contains(&edge_idx) {
+                "red"
+            } else if dead_ends.contains(edge_idx) {
+                "grey"
+            } else {
+                "black"
+            };
+            let cardinality = match edge_docids_len(edge_idx) {
+                Some(len) => format!(" ({len} docids)"),
+                None => String::new(),
+            };
+            writeln!(
+                desc,
+                "{id_to} [label = \"{from_node}→{to_node} [{cost}]{cardinality}\", color = {color}];"
+            )
+            .unwrap();
+            writeln!(desc, "{id_from} -> {id_to} [color = {color}];").unwrap();
 
-            rest.graphviz_rec(desc, path_to, graph);
+            rest.graphviz_rec(desc, path_to, graph, highlighted_path, dead_ends, edge_docids_len);
         }
     }
 }
 
 impl<G: RankingRuleGraphTrait> RankingRuleGraph<G> {
-    pub fn graphviz_with_path(&self, path: &Path) -> String {
+    /// Render the whole graph as a DOT graph, highlighting `path` in red and
+    /// greying out any edge already known to be a dead end. `edge_docids_len`
+    /// annotates an edge with the cardinality of its resolved docids, when known.
+    pub fn graphviz_with_path(
+        &self,
+        path: &Path,
+        dead_ends: &RoaringBitmap,
+        edge_docids_len: &impl Fn(u32) -> Option<u64>,
+    ) -> String {
         let mut desc = String::new();
         desc.push_str("digraph G {\nrankdir = LR;\nnode [shape = \"record\"]\n");
 
@@ -249,17 +303,28 @@ impl<G: RankingRuleGraphTrait> RankingRuleGraph<G> {
         for (edge_idx, edge) in self.all_edges.iter().enumerate() {
             let Some(edge) = edge else { continue };
             let Edge { from_node, to_node, cost, details } = edge;
-            let color = if path.edges.contains(&(edge_idx as u32)) { "red" } else { "green" };
+            let edge_idx = edge_idx as u32;
+            let color = if path.edges.contains(&edge_idx) {
+                "red"
+            } else if dead_ends.contains(edge_idx) {
+                "grey"
+            } else {
+                "green"
+            };
+            let cardinality = match edge_docids_len(edge_idx) {
+                Some(len) => format!(" {len} docids"),
+                None => String::new(),
+            };
             match &edge.details {
                 EdgeDetails::Unconditional => {
                     desc.push_str(&format!(
-                        "{from_node} -> {to_node} [label = \"cost {cost}\", color = {color}];\n",
+                        "{from_node} -> {to_node} [label = \"cost {cost}{cardinality}\", color = {color}];\n",
                         cost = edge.cost,
                     ));
                 }
                 EdgeDetails::Data(details) => {
                     desc.push_str(&format!(
-                        "{from_node} -> {to_node} [label = \"cost {cost} {edge_label}\", color = {color}];\n",
+                        "{from_node} -> {to_node} [label = \"cost {cost} {edge_label}{cardinality}\", color = {color}];\n",
                         cost = edge.cost,
                         edge_label = G::graphviz_edge_details_label(details),
                     ));
@@ -270,4 +335,426 @@ impl<G: RankingRuleGraphTrait> RankingRuleGraph<G> {
         desc.push('}');
         desc
     }
+
+    /// Enumerate the `k` cheapest loopless paths from the root node to the end node,
+    /// using Yen's algorithm on top of the single-source shortest path search.
+    ///
+    /// The first path is the overall cheapest path. Every subsequent path is obtained by
+    /// taking a prefix (the "root path") of a previously accepted path, forbidding the
+    /// nodes that prefix visits and the edges that accepted paths sharing it take right
+    /// after it, and re-running the cheapest-path
+    /// search from the node the prefix ends at (the "spur node"). This guarantees the
+    /// result never contains loops and that paths are produced in non-decreasing cost
+    /// order.
+    ///
+    /// The search itself is delegated to [`k_cheapest_paths_among`], which only deals
+    /// with plain edge weights: that keeps it testable without having to build a real
+    /// [`RankingRuleGraph`].
+    pub fn k_cheapest_paths(&self, k: usize) -> PathsMap<u64> {
+        let paths = k_cheapest_paths_among(
+            &self.search_edges(),
+            self.query_graph.nodes.len(),
+            self.query_graph.root_node,
+            self.query_graph.end_node,
+            k,
+        );
+        PathsMap::from_paths(&paths)
+    }
+
+    fn search_edges(&self) -> Vec<Option<SearchEdge>> {
+        self.all_edges
+            .iter()
+            .map(|edge| {
+                edge.as_ref().map(|edge| SearchEdge {
+                    from_node: edge.from_node,
+                    to_node: edge.to_node,
+                    cost: edge.cost as u64,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The subset of [`Edge`]'s fields the shortest-path search actually needs.
+///
+/// Keeping the search over this minimal, `RankingRuleGraph`-agnostic shape means
+/// [`k_cheapest_paths_among`] and [`cheapest_path_among`] can be exercised directly
+/// in unit tests, instead of only through a full ranking rule graph.
+#[derive(Debug, Clone, Copy)]
+struct SearchEdge {
+    from_node: u32,
+    to_node: u32,
+    cost: u64,
+}
+
+/// The actual implementation behind [`RankingRuleGraph::k_cheapest_paths`]; see its
+/// documentation for the algorithm description.
+fn k_cheapest_paths_among(
+    edges: &[Option<SearchEdge>],
+    node_count: usize,
+    root_node: u32,
+    end_node: u32,
+    k: usize,
+) -> Vec<Path> {
+    if k == 0 {
+        return vec![];
+    }
+
+    let Some(cheapest) = cheapest_path_among(
+        edges,
+        node_count,
+        root_node,
+        end_node,
+        &RoaringBitmap::new(),
+        &HashSet::new(),
+    ) else {
+        return vec![];
+    };
+
+    let mut accepted = vec![cheapest];
+    let mut accepted_map = PathsMap::default();
+    accepted_map.add_path(&accepted[0]);
+
+    // Candidates that have been found but not yet accepted, kept unsorted and
+    // picked from by linear scan since k is expected to be small.
+    let mut candidates: Vec<Path> = vec![];
+
+    while accepted.len() < k {
+        let prev_path = accepted.last().unwrap();
+        for i in 0..prev_path.edges.len() {
+            let root_path = &prev_path.edges[0..i];
+            let spur_node = if i == 0 {
+                root_node
+            } else {
+                edges[root_path[i - 1] as usize].unwrap().to_node
+            };
+
+            let forbidden_edges: RoaringBitmap =
+                accepted_map.edge_indices_after_prefix(root_path).into_iter().collect();
+            let forbidden_nodes: HashSet<u32> = root_path
+                .iter()
+                .map(|&edge_idx| edges[edge_idx as usize].unwrap().from_node)
+                .collect();
+
+            let Some(spur_path) = cheapest_path_among(
+                edges,
+                node_count,
+                spur_node,
+                end_node,
+                &forbidden_edges,
+                &forbidden_nodes,
+            ) else {
+                // The spur node is disconnected from the end node once the forbidden
+                // edges/nodes are removed: no candidate to add for this spur index.
+                continue;
+            };
+
+            let mut path_edges = root_path.to_vec();
+            path_edges.extend(spur_path.edges);
+            let root_cost =
+                root_path.iter().map(|&edge_idx| edges[edge_idx as usize].unwrap().cost).sum::<u64>();
+            let candidate = Path { edges: path_edges, cost: root_cost + spur_path.cost };
+
+            if accepted_map.contains_prefix_of_path(&candidate.edges)
+                || candidates.iter().any(|c| c.edges == candidate.edges)
+            {
+                continue;
+            }
+            candidates.push(candidate);
+        }
+
+        let Some(best_idx) = candidates
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.cost.cmp(&b.cost).then_with(|| a.edges.cmp(&b.edges)))
+            .map(|(idx, _)| idx)
+        else {
+            // B is empty: there aren't k loopless paths in the graph.
+            break;
+        };
+        let next = candidates.remove(best_idx);
+        accepted_map.add_path(&next);
+        accepted.push(next);
+    }
+
+    accepted
+}
+
+/// Find the cheapest path from `from_node` to `end_node`, ignoring any edge in
+/// `forbidden_edges` and any edge leading to a node in `forbidden_nodes`.
+/// Among equally cheap paths, the one whose edge indices compare lowest
+/// lexicographically is returned.
+///
+/// This is a standard Dijkstra search: the ranking rule graph is a DAG with
+/// non-negative edge costs, so a binary-heap-based search is sufficient and avoids
+/// the additional bookkeeping a Bellman-Ford-style relaxation would need.
+fn cheapest_path_among(
+    edges: &[Option<SearchEdge>],
+    node_count: usize,
+    from_node: u32,
+    end_node: u32,
+    forbidden_edges: &RoaringBitmap,
+    forbidden_nodes: &HashSet<u32>,
+) -> Option<Path> {
+    let mut dist = vec![u64::MAX; node_count];
+    let mut best_path: Vec<Vec<u32>> = vec![vec![]; node_count];
+    dist[from_node as usize] = 0;
+
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse((0u64, from_node)));
+
+    while let Some(Reverse((cost, node))) = heap.pop() {
+        if cost > dist[node as usize] {
+            continue;
+        }
+        if node == end_node {
+            continue;
+        }
+        for (edge_idx, edge) in edges.iter().enumerate() {
+            let Some(edge) = edge else { continue };
+            if edge.from_node != node {
+                continue;
+            }
+            if forbidden_edges.contains(edge_idx as u32) || forbidden_nodes.contains(&edge.to_node)
+            {
+                continue;
+            }
+            let next_cost = cost + edge.cost;
+            let to = edge.to_node as usize;
+            if next_cost > dist[to] {
+                continue;
+            }
+            let mut next_path = best_path[node as usize].clone();
+            next_path.push(edge_idx as u32);
+            if next_cost < dist[to] || next_path < best_path[to] {
+                dist[to] = next_cost;
+                best_path[to] = next_path;
+                heap.push(Reverse((next_cost, edge.to_node)));
+            }
+        }
+    }
+
+    if dist[end_node as usize] == u64::MAX {
+        return None;
+    }
+
+    Some(Path { edges: std::mem::take(&mut best_path[end_node as usize]), cost: dist[end_node as usize] })
+}
+
+#[cfg(test)]
+mod cheapest_paths_tests {
+    use super::*;
+
+    // A small DAG with two loopless paths tying for cheapest, and a third, pricier
+    // one, used to exercise Yen's algorithm end to end:
+    //
+    //       ,--e0(1)--> 1 --e4(1)--> 2--.
+    //      /             \              \
+    //     0                `-e1(5)---.   e3(2)
+    //      \                          v   v
+    //       `-------e2(2)-----------> 2 -'-> 3
+    //
+    // (edge indices and costs in parentheses; node 2 is reached either via 0->2
+    // directly or via 0->1->2)
+    const ROOT: u32 = 0;
+    const END: u32 = 3;
+    const NODE_COUNT: usize = 4;
+
+    fn sample_edges() -> Vec<Option<SearchEdge>> {
+        vec![
+            Some(SearchEdge { from_node: 0, to_node: 1, cost: 1 }), // 0: 0 -> 1
+            Some(SearchEdge { from_node: 1, to_node: 3, cost: 5 }), // 1: 1 -> 3
+            Some(SearchEdge { from_node: 0, to_node: 2, cost: 2 }), // 2: 0 -> 2
+            Some(SearchEdge { from_node: 2, to_node: 3, cost: 2 }), // 3: 2 -> 3
+            Some(SearchEdge { from_node: 1, to_node: 2, cost: 1 }), // 4: 1 -> 2
+        ]
+    }
+
+    fn sorted(mut paths: Vec<Path>) -> Vec<(Vec<u32>, u64)> {
+        paths.sort_by(|a, b| a.edges.cmp(&b.edges));
+        paths.into_iter().map(|p| (p.edges, p.cost)).collect()
+    }
+
+    #[test]
+    fn cheapest_path_among_finds_the_minimum_cost_route() {
+        let edges = sample_edges();
+        let path = cheapest_path_among(
+            &edges,
+            NODE_COUNT,
+            ROOT,
+            END,
+            &RoaringBitmap::new(),
+            &HashSet::new(),
+        )
+        .unwrap();
+        // 0->1->2->3 and 0->2->3 tie at cost 4 (0->1->3 costs 6), so assert on cost
+        // and let the k-paths test below cover the tie-break rule.
+        assert_eq!(path.cost, 4);
+    }
+
+    #[test]
+    fn cheapest_path_among_returns_none_when_the_spur_is_disconnected() {
+        let edges = sample_edges();
+        let mut forbidden_edges = RoaringBitmap::new();
+        // Only edge 3 (2 -> 3) reaches the end node from node 2.
+        forbidden_edges.insert(3);
+        assert!(cheapest_path_among(&edges, NODE_COUNT, 2, END, &forbidden_edges, &HashSet::new())
+            .is_none());
+    }
+
+    #[test]
+    fn k_cheapest_paths_among_orders_by_non_decreasing_cost_and_breaks_ties_by_edge_index() {
+        let edges = sample_edges();
+        let paths = k_cheapest_paths_among(&edges, NODE_COUNT, ROOT, END, 3);
+
+        assert_eq!(paths.len(), 3);
+        assert_eq!(paths[0].cost, 4);
+        assert_eq!(paths[1].cost, 4);
+        assert_eq!(paths[2].cost, 6);
+        // The two cost-4 paths ([0, 4, 3] and [2, 3]) are tied: the one whose edges
+        // compare lower lexicographically is accepted first.
+        assert_eq!(paths[0].edges, vec![0, 4, 3]);
+        assert_eq!(paths[1].edges, vec![2, 3]);
+        assert_eq!(paths[2].edges, vec![0, 1]);
+
+        assert_eq!(
+            sorted(paths),
+            vec![(vec![0, 1], 6), (vec![0, 4, 3], 4), (vec![2, 3], 4)]
+        );
+    }
+
+    #[test]
+    fn k_cheapest_paths_among_returns_nothing_when_k_is_zero() {
+        let edges = sample_edges();
+        assert_eq!(k_cheapest_paths_among(&edges, NODE_COUNT, ROOT, END, 0), vec![]);
+    }
+
+    #[test]
+    fn k_cheapest_paths_among_stops_early_when_fewer_than_k_loopless_paths_exist() {
+        let edges = sample_edges();
+        // Only 3 loopless paths exist between the root and the end node.
+        let paths = k_cheapest_paths_among(&edges, NODE_COUNT, ROOT, END, 10);
+        assert_eq!(paths.len(), 3);
+    }
+
+    #[test]
+    fn k_cheapest_paths_among_dedups_candidates_reached_through_different_spurs() {
+        // Two parallel edges from the root lead into the same downstream path, so
+        // the same candidate can be produced from more than one spur index; it must
+        // only be accepted once.
+        let edges = vec![
+            Some(SearchEdge { from_node: 0, to_node: 1, cost: 1 }), // 0: 0 -> 1 (a)
+            Some(SearchEdge { from_node: 0, to_node: 1, cost: 1 }), // 1: 0 -> 1 (b, parallel)
+            Some(SearchEdge { from_node: 1, to_node: 2, cost: 1 }), // 2: 1 -> 2 (end)
+        ];
+        let paths = k_cheapest_paths_among(&edges, 3, 0, 2, 5);
+        // Only the two loopless paths [0, 2] and [1, 2] exist; no duplicates.
+        assert_eq!(sorted(paths), vec![(vec![0, 2], 2), (vec![1, 2], 2)]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_map() -> PathsMap<u64> {
+        let mut map = PathsMap::default();
+        map.insert([0, 1, 2].into_iter(), 3);
+        map.insert([0, 1, 4].into_iter(), 5);
+        map.insert([0, 3].into_iter(), 2);
+        map.insert([2].into_iter(), 1);
+        map
+    }
+
+    fn collect(map: &PathsMap<u64>) -> Vec<(Vec<u32>, u64)> {
+        let mut result = vec![];
+        map.iterate(|path, value| result.push((path.clone(), *value)));
+        result.sort();
+        result
+    }
+
+    #[test]
+    fn insert_and_iterate() {
+        let map = sample_map();
+        assert_eq!(
+            collect(&map),
+            vec![(vec![0, 1, 2], 3), (vec![0, 1, 4], 5), (vec![0, 3], 2), (vec![2], 1)]
+        );
+    }
+
+    #[test]
+    fn insert_overwrites_existing_value_at_the_same_path() {
+        let mut map = sample_map();
+        map.insert([0, 3].into_iter(), 42);
+        assert_eq!(
+            collect(&map),
+            vec![(vec![0, 1, 2], 3), (vec![0, 1, 4], 5), (vec![0, 3], 42), (vec![2], 1)]
+        );
+    }
+
+    #[test]
+    fn contains_prefix_of_path() {
+        let map = sample_map();
+        // Exact match and strict extension of a stored path both count as a prefix.
+        assert!(map.contains_prefix_of_path(&[0, 1, 2]));
+        assert!(map.contains_prefix_of_path(&[0, 1, 2, 99]));
+        // A path that hasn't reached a stored value yet is not a match...
+        assert!(!map.contains_prefix_of_path(&[0, 1]));
+        // ...nor is one that diverges from every branch.
+        assert!(!map.contains_prefix_of_path(&[0, 9]));
+        assert!(map.contains_prefix_of_path(&[2]));
+        assert!(map.contains_prefix_of_path(&[2, 7]));
+    }
+
+    #[test]
+    fn edge_indices_after_prefix() {
+        let map = sample_map();
+        assert_eq!(map.edge_indices_after_prefix(&[]), vec![0, 2]);
+        assert_eq!(map.edge_indices_after_prefix(&[0]), vec![1, 3]);
+        assert_eq!(map.edge_indices_after_prefix(&[0, 1]), vec![2, 4]);
+        // A leaf has no further edges, and an absent prefix has none either.
+        assert_eq!(map.edge_indices_after_prefix(&[2]), Vec::<u32>::new());
+        assert_eq!(map.edge_indices_after_prefix(&[9]), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn remove_edge_drops_every_path_through_it() {
+        let mut map = sample_map();
+        map.remove_edge(&1);
+        assert_eq!(collect(&map), vec![(vec![0, 3], 2), (vec![2], 1)]);
+    }
+
+    #[test]
+    fn remove_edges_drops_the_union_of_forbidden_paths() {
+        let mut map = sample_map();
+        let mut forbidden = RoaringBitmap::new();
+        forbidden.insert(3);
+        forbidden.insert(4);
+        map.remove_edges(&forbidden);
+        assert_eq!(collect(&map), vec![(vec![0, 1, 2], 3), (vec![2], 1)]);
+    }
+
+    #[test]
+    fn remove_prefix_only_drops_paths_sharing_it() {
+        let mut map = sample_map();
+        map.remove_prefix(&[0, 1]);
+        assert_eq!(collect(&map), vec![(vec![0, 3], 2), (vec![2], 1)]);
+    }
+
+    #[test]
+    fn remove_prefix_of_empty_slice_clears_everything() {
+        let mut map = sample_map();
+        map.remove_prefix(&[]);
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn remove_first_returns_paths_in_ascending_edge_order() {
+        let mut map = sample_map();
+        assert_eq!(map.remove_first(), Some((vec![0, 1, 2], 3)));
+        assert_eq!(map.remove_first(), Some((vec![0, 1, 4], 5)));
+        assert_eq!(map.remove_first(), Some((vec![0, 3], 2)));
+        assert_eq!(map.remove_first(), Some((vec![2], 1)));
+        assert_eq!(map.remove_first(), None);
+    }
 }
\ No newline at end of file